@@ -49,18 +49,58 @@ impl Vec3 {
 pub struct McWorld {
     palette_index: HashMap<String, u16>,
     palette: Vec<String>,
-    chunks: HashMap<Vec2, Vec<u16>>,
+    chunks: HashMap<Vec2, ChunkColumn>,
 }
 
 const HEIGHT: u16 = 384;
+const SECTION: usize = 16 * 16 * 16;
+const SECTION_COUNT: usize = HEIGHT as usize / 16;
+
+/// A single 16-wide chunk column stored as 16×16×16 sections keyed by their
+/// Y index. A section is only allocated once it holds a non-air block, so the
+/// all-air sections of a surface build's columns cost no block storage instead
+/// of being part of a dense `16 * 384 * 16` array. Air reads back as palette index 0 (see
+/// [`McWorld::new`]).
+struct ChunkColumn {
+    sections: Vec<Option<Box<[u16; SECTION]>>>,
+}
+
+/// Flattens a block's position inside a section, laid out `z`-major, then
+/// `x`, then `y`.
+fn section_index(x: usize, y: usize, z: usize) -> usize {
+    (z * 16 + x) * 16 + y
+}
+
+impl ChunkColumn {
+    fn new() -> ChunkColumn {
+        ChunkColumn {
+            sections: (0..SECTION_COUNT).map(|_| None).collect(),
+        }
+    }
+
+    /// Records `value` at the chunk-local position, allocating the section it
+    /// lands in if this is the first block stored there.
+    fn set(&mut self, x: usize, y: usize, z: usize, value: u16) {
+        let section = &mut self.sections[y / 16];
+        let blocks = section.get_or_insert_with(|| Box::new([0u16; SECTION]));
+        blocks[section_index(x, y % 16, z)] = value;
+    }
+}
 
 #[wasm_bindgen]
 impl McWorld {
     #[wasm_bindgen(constructor)]
     pub fn new() -> McWorld {
+        let mut palette_index = HashMap::new();
+        let mut palette = Vec::new();
+        // Reserve index 0 for air so that unallocated section slots read back
+        // as air without an extra lookup.
+        palette_index.insert("minecraft:air".to_string(), 0u16);
+        palette.push("minecraft:air".to_string());
+
         McWorld {
-            palette_index: HashMap::new(),
-            palette: Vec::new(),
+            palette_index,
+            palette,
             chunks: HashMap::new(),
         }
     }
@@ -125,34 +165,39 @@ impl McWorld {
                 console_log!("end chunk transforming {:?}", after);
                 console_log!("took {:?}", after - before);
 
-                let mut block_data = vec![0u16; (16 * (HEIGHT as u32) * 16) as usize];
+                let mut block_data = ChunkColumn::new();
 
                 let before = instant::Instant::now();
                 console_log!("start chunk paletting {:?}", before);
-                for i in 0..(16 * (HEIGHT as u32) * 16) {
-                    let chunk_z = i / (16 * HEIGHT as u32);
-                    let remainder = i % (16 * HEIGHT as u32);
-                    let chunk_y = remainder / 16;
-                    let chunk_x = remainder % 16;
-
-                    let Some(block) =
-                        chunk.block(chunk_x as usize, chunk_y as isize, chunk_z as usize)
-                    else {
-                        continue;
-                    };
-
-                    let palette_index =
-                        if let Some(&palette_index) = self.palette_index.get(block.name()) {
-                            palette_index
-                        } else {
-                            let idx = self.palette.len() as u16;
-                            let name = block.name().to_string();
-                            self.palette.push(name.clone());
-                            self.palette_index.insert(name, idx);
-                            idx
-                        };
+                for chunk_z in 0usize..16 {
+                    for chunk_y in 0usize..HEIGHT as usize {
+                        for chunk_x in 0usize..16 {
+                            let Some(block) =
+                                chunk.block(chunk_x, chunk_y as isize, chunk_z)
+                            else {
+                                continue;
+                            };
+
+                            // Leave air as the default index 0 so empty
+                            // sections are never allocated.
+                            if block.name() == "minecraft:air" {
+                                continue;
+                            }
 
-                    block_data[i as usize] = palette_index;
+                            let palette_index =
+                                if let Some(&palette_index) = self.palette_index.get(block.name()) {
+                                    palette_index
+                                } else {
+                                    let idx = self.palette.len() as u16;
+                                    let name = block.name().to_string();
+                                    self.palette.push(name.clone());
+                                    self.palette_index.insert(name, idx);
+                                    idx
+                                };
+
+                            block_data.set(chunk_x, chunk_y, chunk_z, palette_index);
+                        }
+                    }
                 }
                 let after = instant::Instant::now();
                 console_log!("end chunk transforming {:?}", after);
@@ -205,41 +250,48 @@ impl McWorld {
                         continue;
                     };
 
-                    'block: for (i, palette_id) in chunk.iter().enumerate() {
-                        let section_z = i / (16 * HEIGHT as usize);
-                        let remainder = i % (16 * HEIGHT as usize);
-                        let global_y = (remainder / 16) as i32;
-                        let section_x = remainder % 16;
+                    for (sy, section) in chunk.sections.iter().enumerate() {
+                        let Some(blocks) = section else {
+                            continue;
+                        };
 
-                        let global_x = (chunk_x * 16) + section_x as i32;
-                        let global_z = (chunk_z * 16) + section_z as i32;
+                        'block: for (li, palette_id) in blocks.iter().enumerate() {
+                            let local_y = li % 16;
+                            let remainder = li / 16;
+                            let section_x = remainder % 16;
+                            let section_z = remainder / 16;
 
-                        if !is_inside_region(global_x, global_y, global_z) {
-                            continue;
-                        }
+                            let global_y = (sy * 16 + local_y) as i32;
+                            let global_x = (chunk_x * 16) + section_x as i32;
+                            let global_z = (chunk_z * 16) + section_z as i32;
 
-                        let block: &String = self.palette.get(*palette_id as usize).unwrap();
+                            if !is_inside_region(global_x, global_y, global_z) {
+                                continue;
+                            }
 
-                        if block == "minecraft:air" {
-                            continue;
-                        }
+                            let block: &String = self.palette.get(*palette_id as usize).unwrap();
 
-                        for (mc_name, hytopia_id) in rules.iter() {
-                            if glob_match::glob_match(mc_name, block) {
-                                if let Err(err) = js_sys::Reflect::set(
-                                    &terrain,
-                                    &format!(
-                                        "{},{},{}",
-                                        global_x - sub_x,
-                                        global_y - sub_y,
-                                        global_z - sub_z
-                                    )
-                                    .into(),
-                                    &(*hytopia_id as i32).into(),
-                                ) {
-                                    console_log!("err while setting block in terrain: {err:?}")
+                            if block == "minecraft:air" {
+                                continue;
+                            }
+
+                            for (mc_name, hytopia_id) in rules.iter() {
+                                if glob_match::glob_match(mc_name, block) {
+                                    if let Err(err) = js_sys::Reflect::set(
+                                        &terrain,
+                                        &format!(
+                                            "{},{},{}",
+                                            global_x - sub_x,
+                                            global_y - sub_y,
+                                            global_z - sub_z
+                                        )
+                                        .into(),
+                                        &(*hytopia_id as i32).into(),
+                                    ) {
+                                        console_log!("err while setting block in terrain: {err:?}")
+                                    }
+                                    continue 'block;
                                 }
-                                continue 'block;
                             }
                         }
                     }
@@ -280,37 +332,44 @@ impl McWorld {
                         continue;
                     };
 
-                    'block: for (i, palette_id) in chunk.iter().enumerate() {
-                        let section_z = i / (16 * HEIGHT as usize);
-                        let remainder = i % (16 * HEIGHT as usize);
-                        let global_y = (remainder / 16) as i32;
-                        let section_x = remainder % 16;
+                    for (sy, section) in chunk.sections.iter().enumerate() {
+                        let Some(blocks) = section else {
+                            continue;
+                        };
+
+                        'block: for (li, palette_id) in blocks.iter().enumerate() {
+                            let local_y = li % 16;
+                            let remainder = li / 16;
+                            let section_x = remainder % 16;
+                            let section_z = remainder / 16;
 
-                        let global_x = (chunk_x * 16) + section_x as i32;
-                        let global_z = (chunk_z * 16) + section_z as i32;
+                            let global_y = (sy * 16 + local_y) as i32;
+                            let global_x = (chunk_x * 16) + section_x as i32;
+                            let global_z = (chunk_z * 16) + section_z as i32;
 
-                        let block: &String = self.palette.get(*palette_id as usize).unwrap();
+                            let block: &String = self.palette.get(*palette_id as usize).unwrap();
 
-                        if block == "minecraft:air" {
-                            continue;
-                        }
+                            if block == "minecraft:air" {
+                                continue;
+                            }
 
-                        for (mc_name, hytopia_id) in rules.iter() {
-                            if glob_match::glob_match(mc_name, block) {
-                                if let Err(err) = js_sys::Reflect::set(
-                                    &terrain,
-                                    &format!(
-                                        "{},{},{}",
-                                        global_x - sub_x,
-                                        global_y,
-                                        global_z - sub_z
-                                    )
-                                    .into(),
-                                    &(*hytopia_id as i32).into(),
-                                ) {
-                                    console_log!("err while setting block in terrain: {err:?}")
+                            for (mc_name, hytopia_id) in rules.iter() {
+                                if glob_match::glob_match(mc_name, block) {
+                                    if let Err(err) = js_sys::Reflect::set(
+                                        &terrain,
+                                        &format!(
+                                            "{},{},{}",
+                                            global_x - sub_x,
+                                            global_y,
+                                            global_z - sub_z
+                                        )
+                                        .into(),
+                                        &(*hytopia_id as i32).into(),
+                                    ) {
+                                        console_log!("err while setting block in terrain: {err:?}")
+                                    }
+                                    continue 'block;
                                 }
-                                continue 'block;
                             }
                         }
                     }